@@ -0,0 +1,309 @@
+// A simple heuristic autoplayer: builds the legal plays available to a seat and scores
+// them with a small points-style heuristic, for driving bot opponents or surfacing hints.
+
+use crate::{Card, Game, Play};
+
+// bombs/rockets are a last resort - only worth spending unless nothing else will do
+const BOMB_PENALTY: i64 = 1000;
+// cost per card left behind in a rank group that a candidate only partially consumes
+const BROKEN_GROUP_PENALTY: i64 = 10;
+// reward (as negative cost) per card shed, so multi-card plays can outscore singles when
+// leading - enough for the lowest run (3 to 7) to beat leading its single 3, though a run
+// starting at 4 or higher still scores above its own lowest single
+const CARDS_SHED_BONUS: i64 = 6;
+// a following player with more cards than this in hand passes rather than burn a bomb
+const LARGE_HAND_THRESHOLD: usize = 8;
+
+// picks a play for `player_idx`, or `None` to pass
+pub fn suggest_play(game: &Game, player_idx: usize) -> Option<Vec<Card>>
+{
+    let hand = &game.players[player_idx].hand;
+
+    let legal: Vec<(Vec<Card>, Play)> = enumerate_candidates(hand)
+        .into_iter()
+        .filter_map(|mut candidate|
+        {
+            let play = Game::get_play(&mut candidate).ok()?;
+            Game::is_valid_play(&game.play_sequence, &play, game.pass_count).then_some((candidate, play))
+        })
+        .collect();
+
+    if legal.is_empty()
+    {
+        return None;
+    }
+
+    let is_leading = game.play_sequence.last().is_none() || game.pass_count >= 2;
+    if !is_leading
+    {
+        let only_bombs_or_rocket = legal.iter().all(|(_, play)| matches!(play, Play::Bomb(_) | Play::Rocket(_)));
+        if only_bombs_or_rocket && hand.len() > LARGE_HAND_THRESHOLD
+        {
+            return None;
+        }
+    }
+
+    legal.into_iter()
+        .min_by_key(|(candidate, play)| score_candidate(hand, candidate, play))
+        .map(|(candidate, _)| candidate)
+}
+
+fn score_candidate(hand: &[Card], candidate: &[Card], play: &Play) -> i64
+{
+    let mut score = 0i64;
+
+    if matches!(play, Play::Bomb(_) | Play::Rocket(_))
+    {
+        score += BOMB_PENALTY;
+    }
+
+    // penalize leaving cards of a partially-used rank group behind (e.g. playing one card
+    // of a pair as a single wastes the other card's usefulness as a future pair/triple)
+    for (i, card) in candidate.iter().enumerate()
+    {
+        // count each rank group once, however many of its cards the candidate takes
+        if candidate[..i].iter().any(|c| c.value() == card.value())
+        {
+            continue;
+        }
+        let group_size = hand.iter().filter(|c| c.value() == card.value()).count();
+        let used = candidate.iter().filter(|c| c.value() == card.value()).count();
+        score += BROKEN_GROUP_PENALTY * (group_size - used) as i64;
+    }
+
+    score += candidate.iter().map(|c| c.value() as i64).sum::<i64>();
+    score -= candidate.len() as i64 * CARDS_SHED_BONUS;
+
+    score
+}
+
+// every candidate play buildable from the hand: singles, pairs, triples (bare and with an
+// attached single/pair), bombs (bare and with two attached singles/pairs), the rocket, and
+// detected sequences/airplanes. Each is later filtered through `Game::get_play` +
+// `Game::is_valid_play`, so it's fine to offer shapes that turn out illegal right now.
+fn enumerate_candidates(hand: &[Card]) -> Vec<Vec<Card>>
+{
+    let groups = group_by_value(hand);
+    let mut candidates: Vec<Vec<Card>> = Vec::new();
+
+    for (i, group) in groups.iter().enumerate()
+    {
+        let others: Vec<&Vec<Card>> = groups.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, g)| g).collect();
+
+        candidates.push(vec![group[0]]);
+        if group.len() >= 2
+        {
+            candidates.push(group[..2].to_vec());
+        }
+        if group.len() >= 3
+        {
+            candidates.push(group[..3].to_vec());
+
+            if let Some(single) = others.first()
+            {
+                let mut combo = group[..3].to_vec();
+                combo.push(single[0]);
+                candidates.push(combo);
+            }
+            if let Some(pair) = others.iter().find(|g| g.len() >= 2)
+            {
+                let mut combo = group[..3].to_vec();
+                combo.extend_from_slice(&pair[..2]);
+                candidates.push(combo);
+            }
+        }
+        if group.len() == 4
+        {
+            candidates.push(group.clone());
+
+            if others.len() >= 2
+            {
+                let mut combo = group.clone();
+                combo.push(others[0][0]);
+                combo.push(others[1][0]);
+                candidates.push(combo);
+            }
+            let pairs: Vec<&Vec<Card>> = others.iter().copied().filter(|g| g.len() >= 2).collect();
+            if pairs.len() >= 2
+            {
+                let mut combo = group.clone();
+                combo.extend_from_slice(&pairs[0][..2]);
+                combo.extend_from_slice(&pairs[1][..2]);
+                candidates.push(combo);
+            }
+        }
+    }
+
+    if hand.contains(&Card::BLACK_JOKER) && hand.contains(&Card::RED_JOKER)
+    {
+        candidates.push(vec![Card::BLACK_JOKER, Card::RED_JOKER]);
+    }
+
+    candidates.extend(sequence_candidates(&groups));
+    candidates.extend(airplane_candidates(&groups));
+
+    candidates
+}
+
+fn group_by_value(hand: &[Card]) -> Vec<Vec<Card>>
+{
+    let mut sorted = hand.to_vec();
+    sorted.sort();
+
+    let mut groups: Vec<Vec<Card>> = Vec::new();
+    for card in sorted
+    {
+        match groups.iter_mut().find(|group| group[0].value() == card.value())
+        {
+            Some(group) => group.push(card),
+            None => groups.push(vec![card]),
+        }
+    }
+    groups
+}
+
+// a run of 5+ consecutive single ranks (no 2s, no jokers) can be played whole, or as any
+// length-5 window within it, giving the AI both a "shed it all" and a "shed the minimum" option
+fn sequence_candidates(groups: &[Vec<Card>]) -> Vec<Vec<Card>>
+{
+    let eligible: Vec<&Vec<Card>> = groups.iter().filter(|group| group[0].value() < 15).collect();
+    let mut candidates = Vec::new();
+
+    let mut run_start = 0;
+    for i in 1..=eligible.len()
+    {
+        let run_ends = i == eligible.len() || eligible[i][0].value() != eligible[i - 1][0].value() + 1;
+        if run_ends
+        {
+            let run = &eligible[run_start..i];
+            if run.len() >= 5
+            {
+                candidates.push(run.iter().map(|group| group[0]).collect());
+                for window in run.windows(5)
+                {
+                    candidates.push(window.iter().map(|group| group[0]).collect());
+                }
+            }
+            run_start = i;
+        }
+    }
+    candidates
+}
+
+// 2+ consecutive triples (no rank 2, no jokers), offered bare - `Game::get_play` already
+// reads a bare triple-run back as an `Airplane` with no attachments
+fn airplane_candidates(groups: &[Vec<Card>]) -> Vec<Vec<Card>>
+{
+    let eligible: Vec<&Vec<Card>> = groups.iter().filter(|group| group.len() >= 3 && group[0].value() < 15).collect();
+    let mut candidates = Vec::new();
+
+    let mut run_start = 0;
+    for i in 1..=eligible.len()
+    {
+        let run_ends = i == eligible.len() || eligible[i][0].value() != eligible[i - 1][0].value() + 1;
+        if run_ends
+        {
+            let run = &eligible[run_start..i];
+            if run.len() >= 2
+            {
+                candidates.push(run.iter().flat_map(|group| group[..3].iter().copied()).collect());
+            }
+            run_start = i;
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::{Rank, Suit};
+
+    fn card(rank: Rank, suit: Suit) -> Card
+    {
+        Card::new(rank, suit)
+    }
+
+    fn spades(rank: Rank) -> Card
+    {
+        card(rank, Suit::Spades)
+    }
+
+    fn game_with_hand(hand: Vec<Card>) -> Game
+    {
+        let mut game = Game::new();
+        game.players[0].hand = hand;
+        game
+    }
+
+    #[test]
+    fn leads_with_something_when_hand_is_nonempty()
+    {
+        let game = game_with_hand(vec![spades(Rank::Four)]);
+        assert!(suggest_play(&game, 0) == Some(vec![spades(Rank::Four)]));
+    }
+
+    #[test]
+    fn passes_on_an_empty_hand()
+    {
+        let game = game_with_hand(Vec::new());
+        assert!(suggest_play(&game, 0).is_none());
+    }
+
+    #[test]
+    fn prefers_the_pair_over_breaking_it_into_a_single_when_leading()
+    {
+        let game = game_with_hand(vec![spades(Rank::Seven), card(Rank::Seven, Suit::Hearts)]);
+        let play = suggest_play(&game, 0).unwrap();
+        assert_eq!(play.len(), 2);
+    }
+
+    #[test]
+    fn follows_with_the_smallest_legal_beat()
+    {
+        let mut game = game_with_hand(vec![spades(Rank::Eight), spades(Rank::King)]);
+        game.play_sequence.push(Play::Single(spades(Rank::Four)));
+        let play = suggest_play(&game, 0).unwrap();
+        assert!(play == vec![spades(Rank::Eight)]);
+    }
+
+    #[test]
+    fn passes_rather_than_spend_a_bomb_with_a_large_hand()
+    {
+        let hand = vec![
+            spades(Rank::Nine), card(Rank::Nine, Suit::Hearts),
+            card(Rank::Nine, Suit::Diamonds), card(Rank::Nine, Suit::Clubs),
+            spades(Rank::Four), spades(Rank::Five), spades(Rank::Six),
+            spades(Rank::Ten), spades(Rank::Jack), spades(Rank::Queen),
+        ];
+        let mut game = game_with_hand(hand);
+        game.play_sequence.push(Play::Single(spades(Rank::Two)));
+        assert!(suggest_play(&game, 0).is_none());
+    }
+
+    #[test]
+    fn bombs_when_it_is_the_only_legal_reply_and_the_hand_is_small()
+    {
+        let hand = vec![
+            spades(Rank::Nine), card(Rank::Nine, Suit::Hearts),
+            card(Rank::Nine, Suit::Diamonds), card(Rank::Nine, Suit::Clubs),
+        ];
+        let mut game = game_with_hand(hand.clone());
+        game.play_sequence.push(Play::Single(spades(Rank::Two)));
+        let play = suggest_play(&game, 0).unwrap();
+        assert!(matches!(Game::get_play(&mut play.clone()), Ok(Play::Bomb(_))));
+    }
+
+    #[test]
+    fn prefers_a_run_over_single_cards_when_leading()
+    {
+        let hand = vec![
+            spades(Rank::Three), spades(Rank::Four), spades(Rank::Five),
+            spades(Rank::Six), spades(Rank::Seven),
+        ];
+        let game = game_with_hand(hand.clone());
+        let play = suggest_play(&game, 0).unwrap();
+        assert_eq!(play.len(), 5);
+    }
+}
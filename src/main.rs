@@ -1,46 +1,129 @@
 use rand::{thread_rng, prelude::SliceRandom};
-use std::collections::HashMap;
+#[cfg(feature = "server")]
+use serde::{Serialize, Deserialize};
+use std::mem;
 
-macro_rules! sort_field_mode {
-    ($vec:ident, $main_field:ident, $second_field:ident) => {
-        let mut counts = HashMap::new();
-        for item in $vec.iter()
-        {
-            let count = counts.entry(item.$main_field.clone()).or_insert(0);
-            *count += 1;
-        }
-        $vec.sort_by(|a, b| {
-            let count_a = counts.get(&a.$main_field).unwrap();
-            let count_b = counts.get(&b.$main_field).unwrap();
-            if count_a == count_b
-            {
-                a.$second_field.cmp(&b.$second_field)
-            }
-            else
-            {
-                count_a.cmp(count_b)
-            }
-        });
-    };
+#[cfg(feature = "server")]
+mod server;
+// a public surface for bot opponents/hints; nothing in this crate calls it yet
+#[allow(dead_code)]
+mod ai;
+
+pub const NUM_RANKS: usize = 13;
+pub const NUM_SUITS: usize = 4;
+pub const NUM_CARDS: usize = NUM_RANKS * NUM_SUITS + 2; // + the two jokers
+
+// the score baseline every player starts a match from, before any settling happens
+pub const STARTING_PROGRESS: i32 = 0;
+
+#[cfg_attr(feature = "server", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rank
+{
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+    Two,
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "server", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Suit
 {
     Spades,
     Hearts,
     Diamonds,
     Clubs,
-    Joker,
 }
 
-#[derive(Clone, PartialEq, Eq)]
-pub struct Card
+// a card packed into a single byte: `rank = byte >> 2`, `suit = byte & 3`. The two byte
+// values past NUM_RANKS * NUM_SUITS are the jokers. Deriving Ord on the raw byte gives us
+// play-strength ordering for free, since rank occupies the high bits: 3<4<...<K<A<2, and
+// both jokers (which live past every real rank) sort above everything else.
+#[cfg_attr(feature = "server", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Card(pub u8);
+
+impl Card
 {
-    pub rank: u8,
-    pub suit: Suit,
+    pub fn new(rank: Rank, suit: Suit) -> Self
+    {
+        Card(((rank as u8) << 2) | suit as u8)
+    }
+
+    pub const BLACK_JOKER: Card = Card((NUM_RANKS * NUM_SUITS) as u8);
+    pub const RED_JOKER: Card = Card((NUM_RANKS * NUM_SUITS) as u8 + 1);
+
+    pub fn rank(&self) -> Option<Rank>
+    {
+        if self.is_joker()
+        {
+            return None;
+        }
+        Some(match self.0 >> 2
+        {
+            0 => Rank::Three,
+            1 => Rank::Four,
+            2 => Rank::Five,
+            3 => Rank::Six,
+            4 => Rank::Seven,
+            5 => Rank::Eight,
+            6 => Rank::Nine,
+            7 => Rank::Ten,
+            8 => Rank::Jack,
+            9 => Rank::Queen,
+            10 => Rank::King,
+            11 => Rank::Ace,
+            12 => Rank::Two,
+            _ => unreachable!(),
+        })
+    }
+
+    pub fn suit(&self) -> Option<Suit>
+    {
+        if self.is_joker()
+        {
+            return None;
+        }
+        Some(match self.0 & 3
+        {
+            0 => Suit::Spades,
+            1 => Suit::Hearts,
+            2 => Suit::Diamonds,
+            3 => Suit::Clubs,
+            _ => unreachable!(),
+        })
+    }
+
+    pub fn is_joker(&self) -> bool
+    {
+        self.0 as usize >= NUM_RANKS * NUM_SUITS
+    }
+
+    // total play-strength value: 3<4<...<10<J<Q<K<A<2<black joker<red joker
+    pub fn value(&self) -> u8
+    {
+        if self.is_joker()
+        {
+            16 + (self.0 - (NUM_RANKS * NUM_SUITS) as u8)
+        }
+        else
+        {
+            (self.0 >> 2) + 3
+        }
+    }
 }
 
+#[cfg_attr(feature = "server", derive(Serialize, Deserialize))]
 #[derive(Clone, PartialEq, Eq)]
 pub enum Play
 {
@@ -57,9 +140,10 @@ pub enum Play
         triple: Vec<Card>,
         double: Vec<Card>,
     },
-    Airplane // consecutive triples (excl. rank 2) (any number of triples)
+    Airplane // consecutive triples (excl. rank 2), optionally with attached singles/pairs
     {
         triples: Vec<Vec<Card>>,
+        attachments: Vec<Card>,
     },
     QuadTwoSingle
     {
@@ -75,21 +159,12 @@ pub enum Play
     },
     Bomb(Vec<Card>),
     Sequence(Vec<Card>), // cards 3-A in a sequence
+    Rocket(Vec<Card>), // both jokers
 }
 
 pub fn get_deck() -> Vec<Card>
 {
-    let mut deck = Vec::new();
-    for rank in 1..14
-    {
-        for suit in [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs].iter()
-        {
-            deck.push(Card { rank, suit: suit.clone() });
-        }
-    }
-    deck.push(Card { rank: 1, suit: Suit::Joker });
-    deck.push(Card { rank: 2, suit: Suit::Joker });
-    deck
+    (0..NUM_CARDS as u8).map(Card).collect()
 }
 
 pub struct Player
@@ -106,10 +181,20 @@ pub struct Game
     pub center_pile: Vec<Card>,
     pub winner: Option<usize>,
     pub landlord: Option<usize>,
+    pub highest_bid: u8,
+    pub highest_bidder: Option<usize>,
+    pub bid_pass_count: u8,
+    pub scores: [i32; 3],
+    pub multiplier: u32,
+    pub landlord_has_played: bool,
+    pub peasant_has_played: bool,
 }
 
 impl Game
 {
+    // only reached from tests and the (optional) server module, which isn't part of the
+    // default feature set
+    #[allow(dead_code)]
     fn new() -> Self
     {
         // set up game state
@@ -125,18 +210,18 @@ impl Game
         deck.shuffle(&mut thread_rng());
 
         // deal cards for each player
-        for i in 0..3
+        for player in players.iter_mut()
         {
             for _ in 0..17
             {
-                players[i].hand.push(deck.pop().unwrap());
+                player.hand.push(deck.pop().unwrap());
             }
         }
 
         // put the rest of the deck in the center pile
         for card in deck.iter()
         {
-            center_pile.push(card.clone());
+            center_pile.push(*card);
         }
 
         // return the new game
@@ -148,37 +233,117 @@ impl Game
             center_pile,
             winner: None,
             landlord: None,
+            highest_bid: 0,
+            highest_bidder: None,
+            bid_pass_count: 0,
+            scores: [STARTING_PROGRESS; 3],
+            multiplier: 1,
+            landlord_has_played: false,
+            peasant_has_played: false,
         }
     }
 
-    pub fn take_landlord(&mut self, player_idx: usize) -> Result<(), String>
+    pub fn bid(&mut self, player_idx: usize, amount: u8) -> Result<(), String>
     {
+        // make sure bidding hasn't already finished
+        if self.landlord.is_some()
+        {
+            return Err("Bidding has already ended".to_string());
+        }
         // make sure the player is valid
         if player_idx >= 3
         {
             return Err("Invalid player index".to_string());
         }
-        // make sure the pile exists
-        if self.center_pile.len() == 0
+        // make sure it's this player's turn to bid
+        if player_idx != self.current_turn_idx
+        {
+            return Err("Not this player's turn to bid".to_string());
+        }
+        // a bid must be 1-3 and must beat the current highest bid
+        if amount == 0 || amount > 3
         {
-            return Err("Center pile is empty".to_string());
+            return Err("Bid must be between 1 and 3".to_string());
         }
+        if amount <= self.highest_bid
+        {
+            return Err("Bid must exceed the current highest bid".to_string());
+        }
+
+        self.highest_bid = amount;
+        self.highest_bidder = Some(player_idx);
+        self.bid_pass_count = 0;
 
-        // add the center pile to the player's hand
+        // a bid of 3 can't be topped, so it ends the auction immediately
+        if amount == 3
+        {
+            self.finish_bidding();
+        }
+        else
+        {
+            self.current_turn_idx = (self.current_turn_idx + 1) % 3;
+        }
+        Ok(())
+    }
+
+    pub fn pass_bid(&mut self, player_idx: usize) -> Result<(), String>
+    {
+        // make sure bidding hasn't already finished
+        if self.landlord.is_some()
+        {
+            return Err("Bidding has already ended".to_string());
+        }
+        // make sure the player is valid
+        if player_idx >= 3
+        {
+            return Err("Invalid player index".to_string());
+        }
+        // make sure it's this player's turn to bid
+        if player_idx != self.current_turn_idx
+        {
+            return Err("Not this player's turn to bid".to_string());
+        }
+
+        self.bid_pass_count += 1;
+        if self.highest_bidder.is_some() && self.bid_pass_count >= 2
+        {
+            // the other two players both passed on the standing bid - it wins
+            self.finish_bidding();
+        }
+        else if self.highest_bidder.is_none() && self.bid_pass_count >= 3
+        {
+            // nobody bid at all - the first player takes the landlord seat with the minimum bid
+            self.highest_bid = 1;
+            self.highest_bidder = Some(0);
+            self.finish_bidding();
+        }
+        else
+        {
+            self.current_turn_idx = (self.current_turn_idx + 1) % 3;
+        }
+        Ok(())
+    }
+
+    fn finish_bidding(&mut self)
+    {
+        let landlord = self.highest_bidder.unwrap();
+
+        // add the center pile to the landlord's hand
         for card in self.center_pile.iter()
         {
-            self.players[player_idx].hand.push(card.clone());
+            self.players[landlord].hand.push(*card);
         }
-        // clear the center pile
         self.center_pile.clear();
-        // set the current turn to the player
-        self.current_turn_idx = player_idx;
+
+        // the bid value seeds the base multiplier
+        self.multiplier = self.highest_bid as u32;
+        // set the current turn to the landlord
+        self.current_turn_idx = landlord;
         // set the landlord
-        self.landlord = Some(player_idx);
-        Ok(())
+        self.landlord = Some(landlord);
     }
 
-    pub fn play_cards(&mut self, player_idx: usize, cards: &mut Vec<Card>) -> Result<(), String>
+    pub fn play_cards(&mut self, player_idx: usize, cards: &mut [Card]) -> Result<(), String>
     {
         // make sure game isn't won
         if self.winner.is_some()
@@ -190,6 +355,11 @@ impl Game
         {
             return Err("Invalid player index".to_string());
         }
+        // make sure it's this player's turn to play
+        if player_idx != self.current_turn_idx
+        {
+            return Err("Not this player's turn".to_string());
+        }
         // make sure the player has the cards
         for card in cards.iter()
         {
@@ -201,7 +371,7 @@ impl Game
 
         // get play from cards
         let play = Self::get_play(cards)?;
-        
+
         // make sure the cards are valid
         if !Self::is_valid_play(&self.play_sequence, &play, self.pass_count)
         {
@@ -213,6 +383,20 @@ impl Game
             let idx = self.players[player_idx].hand.iter().position(|x| *x == *card).unwrap();
             self.players[player_idx].hand.remove(idx);
         }
+        // a bomb or rocket doubles the stake
+        if matches!(play, Play::Bomb(_) | Play::Rocket(_))
+        {
+            self.multiplier *= 2;
+        }
+        // track whether the landlord/peasants have played yet, for spring detection
+        if Some(player_idx) == self.landlord
+        {
+            self.landlord_has_played = true;
+        }
+        else
+        {
+            self.peasant_has_played = true;
+        }
         // set the current sequence
         self.play_sequence.push(play);
         // set the current turn to the next player
@@ -221,9 +405,10 @@ impl Game
         self.pass_count = 0;
 
         // check if the player won
-        if self.players[player_idx].hand.len() == 0
+        if self.players[player_idx].hand.is_empty()
         {
             self.winner = Some(player_idx);
+            self.settle_scores();
         }
         Ok(())
     }
@@ -240,6 +425,11 @@ impl Game
         {
             return Err("Invalid player index".to_string());
         }
+        // make sure it's this player's turn to play
+        if player_idx != self.current_turn_idx
+        {
+            return Err("Not this player's turn".to_string());
+        }
         // check the player didn't pass twice in a row
         if self.pass_count == 2
         {
@@ -287,26 +477,656 @@ impl Game
         self.winner
     }
 
-    fn get_play(cards: &mut Vec<Card>) -> Result<Play, String>
+    pub fn get_scores(&self) -> [i32; 3]
     {
-        sort_field_mode!(cards, suit, rank);
+        self.scores
+    }
+
+    pub fn get_multiplier(&self) -> u32
+    {
+        self.multiplier
+    }
+
+    // settle scores once `winner` is set: a spring (the loser never got a single play in)
+    // doubles the stake one last time, then the landlord gains/loses base * multiplier
+    // against the combined peasants, and each peasant the inverse
+    fn settle_scores(&mut self)
+    {
+        let landlord = self.landlord.unwrap();
+        let landlord_won = self.winner == Some(landlord);
+
+        let spring = (landlord_won && !self.peasant_has_played)
+            || (!landlord_won && !self.landlord_has_played);
+        if spring
+        {
+            self.multiplier *= 2;
+        }
+
+        let stake = self.highest_bid as i32 * self.multiplier as i32;
+        let landlord_delta = if landlord_won { stake } else { -stake };
+
+        self.scores[landlord] += landlord_delta;
+        for idx in 0..3
+        {
+            if idx != landlord
+            {
+                self.scores[idx] -= landlord_delta;
+            }
+        }
+    }
+
+    fn get_play(cards: &mut [Card]) -> Result<Play, String>
+    {
+        if cards.is_empty()
+        {
+            return Err("Invalid play".to_string());
+        }
+
+        cards.sort();
+
+        // group cards by rank, collapsing the two jokers into their own pseudo-ranks
+        let mut groups: Vec<Vec<Card>> = Vec::new();
+        for card in cards.iter()
+        {
+            match groups.iter_mut().find(|group| group[0].value() == card.value())
+            {
+                Some(group) => group.push(*card),
+                None => groups.push(vec![*card]),
+            }
+        }
+        groups.sort_by_key(|group| group[0].value());
+
+        // rocket: both jokers and nothing else
+        if cards.len() == 2 && cards.iter().all(|card| card.is_joker())
+        {
+            return Ok(Play::Rocket(cards.to_vec()));
+        }
+
+        // a run of 5+ distinct, consecutive ranks (no 2s, no jokers)
+        if cards.len() >= 5 && groups.iter().all(|group| group.len() == 1)
+            && Self::is_consecutive_run(&groups) && groups.last().unwrap()[0].value() < 15
+        {
+            return Ok(Play::Sequence(cards.to_vec()));
+        }
 
-        match cards.len()
+        let mut lens: Vec<usize> = groups.iter().map(|group| group.len()).collect();
+        lens.sort();
+
+        match lens.as_slice()
         {
-            1 => Ok(Play::Single(cards[0].clone())),
-            2 => {
-                unimplemented!();
+            [1] => Ok(Play::Single(cards[0])),
+            [2] => Ok(Play::Pair(cards.to_vec())),
+            [3] => Ok(Play::TripleSolo(cards.to_vec())),
+            [4] => Ok(Play::Bomb(cards.to_vec())),
+            [1, 3] =>
+            {
+                let triple = groups.iter().find(|group| group.len() == 3).unwrap().clone();
+                let single = groups.iter().find(|group| group.len() == 1).unwrap()[0];
+                Ok(Play::TripleSingle { triple, single })
+            },
+            [2, 3] =>
+            {
+                let triple = groups.iter().find(|group| group.len() == 3).unwrap().clone();
+                let double = groups.iter().find(|group| group.len() == 2).unwrap().clone();
+                Ok(Play::TripleDouble { triple, double })
+            },
+            [1, 1, 4] =>
+            {
+                let quad = groups.iter().find(|group| group.len() == 4).unwrap().clone();
+                let mut singles = groups.iter().filter(|group| group.len() == 1);
+                let single_1 = singles.next().unwrap().clone();
+                let single_2 = singles.next().unwrap().clone();
+                Ok(Play::QuadTwoSingle { quad, single_1, single_2 })
             },
-            _ => Err("Invalid play".to_string())
+            [2, 2, 4] =>
+            {
+                let quad = groups.iter().find(|group| group.len() == 4).unwrap().clone();
+                let mut pairs = groups.iter().filter(|group| group.len() == 2);
+                let pair_1 = pairs.next().unwrap().clone();
+                let pair_2 = pairs.next().unwrap().clone();
+                Ok(Play::QuadTwoPair { quad, pair_1, pair_2 })
+            },
+            _ => Self::try_airplane(&groups).ok_or_else(|| "Invalid play".to_string()),
+        }
+    }
+
+    // true if every (single-card) group forms one run of consecutive ranks
+    fn is_consecutive_run(groups: &[Vec<Card>]) -> bool
+    {
+        for window in groups.windows(2)
+        {
+            if window[1][0].value() != window[0][0].value() + 1
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    // recognize an airplane: 2+ consecutive triples, optionally with an equal number of
+    // attached singles or attached pairs (but never a mix of the two)
+    fn try_airplane(groups: &[Vec<Card>]) -> Option<Play>
+    {
+        let mut triples: Vec<Vec<Card>> = groups.iter()
+            .filter(|group| group.len() == 3)
+            .cloned()
+            .collect();
+        triples.sort_by_key(|triple| triple[0].value());
+
+        if triples.len() < 2 || !Self::is_consecutive_run(&triples)
+        {
+            return None;
+        }
+        // rank 2 and the jokers can never be part of a triple run
+        if triples.last().unwrap()[0].value() >= 15
+        {
+            return None;
+        }
+
+        let remainder: Vec<&Vec<Card>> = groups.iter().filter(|group| group.len() != 3).collect();
+        if remainder.is_empty()
+        {
+            return Some(Play::Airplane { triples, attachments: Vec::new() });
+        }
+
+        let kicker_len = remainder[0].len();
+        let is_valid_kicker_shape = (kicker_len == 1 || kicker_len == 2)
+            && remainder.len() == triples.len()
+            && remainder.iter().all(|group| group.len() == kicker_len);
+        if !is_valid_kicker_shape
+        {
+            return None;
+        }
+
+        let attachments: Vec<Card> = remainder.into_iter().flat_map(|group| group.iter().cloned()).collect();
+        Some(Play::Airplane { triples, attachments })
+    }
+
+    fn is_valid_play(sequence: &[Play], play: &Play, pass_count: u8) -> bool
+    {
+        // the trick is fresh (both other players passed, or nobody has played yet): any
+        // well-formed play leads
+        let last_play = match sequence.last()
+        {
+            Some(last_play) if pass_count < 2 => last_play,
+            _ => return true,
+        };
+
+        // a rocket beats everything, and nothing beats a rocket
+        if matches!(play, Play::Rocket(_))
+        {
+            return true;
+        }
+        if matches!(last_play, Play::Rocket(_))
+        {
+            return false;
+        }
+
+        // a bomb beats any non-bomb; between two bombs the higher one wins
+        let play_is_bomb = matches!(play, Play::Bomb(_));
+        let last_is_bomb = matches!(last_play, Play::Bomb(_));
+        if play_is_bomb != last_is_bomb
+        {
+            return play_is_bomb;
+        }
+        if play_is_bomb && last_is_bomb
+        {
+            return Self::play_base_rank(play) > Self::play_base_rank(last_play);
+        }
+
+        // otherwise the new play must match the last play's variant and length, and beat
+        // it by its leading/base rank
+        if Self::play_len(play) != Self::play_len(last_play)
+        {
+            return false;
         }
+        if mem::discriminant(play) != mem::discriminant(last_play)
+        {
+            return false;
+        }
+        Self::play_base_rank(play) > Self::play_base_rank(last_play)
     }
 
-    fn is_valid_play(sequence: &Vec<Play>, play: &Play, pass_count: u8) -> bool
+    // the rank that determines whether one play beats another of the same shape
+    fn play_base_rank(play: &Play) -> u8
     {
-        unimplemented!();
+        match play
+        {
+            Play::Single(card) => card.value(),
+            Play::Pair(cards) | Play::TripleSolo(cards) | Play::Bomb(cards) => cards[0].value(),
+            Play::TripleSingle { triple, .. } | Play::TripleDouble { triple, .. } => triple[0].value(),
+            Play::QuadTwoSingle { quad, .. } | Play::QuadTwoPair { quad, .. } => quad[0].value(),
+            Play::Sequence(cards) => cards[0].value(),
+            Play::Airplane { triples, .. } => triples[0][0].value(),
+            Play::Rocket(cards) => cards[0].value(),
+        }
+    }
+
+    // the number of cards a play is made of
+    fn play_len(play: &Play) -> usize
+    {
+        match play
+        {
+            Play::Single(_) => 1,
+            Play::Pair(cards) | Play::TripleSolo(cards) | Play::Bomb(cards) | Play::Sequence(cards) => cards.len(),
+            Play::TripleSingle { triple, .. } => triple.len() + 1,
+            Play::TripleDouble { triple, double } => triple.len() + double.len(),
+            Play::QuadTwoSingle { quad, single_1, single_2 } => quad.len() + single_1.len() + single_2.len(),
+            Play::QuadTwoPair { quad, pair_1, pair_2 } => quad.len() + pair_1.len() + pair_2.len(),
+            Play::Airplane { triples, attachments } => triples.iter().map(Vec::len).sum::<usize>() + attachments.len(),
+            Play::Rocket(cards) => cards.len(),
+        }
     }
 }
 
+#[cfg(not(feature = "server"))]
 fn main() {
     println!("Hello, world!");
 }
+
+#[cfg(feature = "server")]
+#[async_std::main]
+async fn main() -> std::io::Result<()>
+{
+    server::app().listen("127.0.0.1:8080").await
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // test-only convenience: accept raw 1..13 ranks (as the old Card { rank, suit } did)
+    // instead of spelling out Rank variants everywhere
+    fn rank_from_u8(rank: u8) -> Rank
+    {
+        match rank
+        {
+            1 => Rank::Three,
+            2 => Rank::Four,
+            3 => Rank::Five,
+            4 => Rank::Six,
+            5 => Rank::Seven,
+            6 => Rank::Eight,
+            7 => Rank::Nine,
+            8 => Rank::Ten,
+            9 => Rank::Jack,
+            10 => Rank::Queen,
+            11 => Rank::King,
+            12 => Rank::Ace,
+            13 => Rank::Two,
+            _ => panic!("rank out of range"),
+        }
+    }
+
+    fn card(rank: u8, suit: Suit) -> Card
+    {
+        Card::new(rank_from_u8(rank), suit)
+    }
+
+    fn spades(rank: u8) -> Card
+    {
+        card(rank, Suit::Spades)
+    }
+
+    #[test]
+    fn single()
+    {
+        let mut cards = vec![spades(5)];
+        assert!(matches!(Game::get_play(&mut cards), Ok(Play::Single(_))));
+    }
+
+    #[test]
+    fn pair()
+    {
+        let mut cards = vec![spades(7), card(7, Suit::Hearts)];
+        assert!(matches!(Game::get_play(&mut cards), Ok(Play::Pair(_))));
+    }
+
+    #[test]
+    fn mismatched_pair_is_invalid()
+    {
+        let mut cards = vec![spades(7), card(8, Suit::Hearts)];
+        assert!(Game::get_play(&mut cards).is_err());
+    }
+
+    #[test]
+    fn triple_solo()
+    {
+        let mut cards = vec![spades(9), card(9, Suit::Hearts), card(9, Suit::Diamonds)];
+        assert!(matches!(Game::get_play(&mut cards), Ok(Play::TripleSolo(_))));
+    }
+
+    #[test]
+    fn triple_single()
+    {
+        let mut cards = vec![spades(9), card(9, Suit::Hearts), card(9, Suit::Diamonds), spades(4)];
+        match Game::get_play(&mut cards)
+        {
+            Ok(Play::TripleSingle { triple, single }) =>
+            {
+                assert_eq!(triple.len(), 3);
+                assert_eq!(single.value(), spades(4).value());
+            },
+            other => panic!("expected TripleSingle, got {:?}", other.is_ok()),
+        }
+    }
+
+    #[test]
+    fn triple_double()
+    {
+        let mut cards = vec![
+            spades(9), card(9, Suit::Hearts), card(9, Suit::Diamonds),
+            spades(4), card(4, Suit::Hearts),
+        ];
+        assert!(matches!(Game::get_play(&mut cards), Ok(Play::TripleDouble { .. })));
+    }
+
+    #[test]
+    fn bomb()
+    {
+        let mut cards = vec![
+            spades(9), card(9, Suit::Hearts), card(9, Suit::Diamonds), card(9, Suit::Clubs),
+        ];
+        assert!(matches!(Game::get_play(&mut cards), Ok(Play::Bomb(_))));
+    }
+
+    #[test]
+    fn rocket()
+    {
+        let mut cards = vec![Card::BLACK_JOKER, Card::RED_JOKER];
+        assert!(matches!(Game::get_play(&mut cards), Ok(Play::Rocket(_))));
+    }
+
+    #[test]
+    fn four_of_a_rank_is_a_bomb_not_a_triple_plus_single()
+    {
+        // the "kicker must not itself be part of the core group" edge case: four cards of
+        // the same rank must never be read as a triple with a leftover single of that rank
+        let mut cards = vec![
+            spades(7), card(7, Suit::Hearts), card(7, Suit::Diamonds), card(7, Suit::Clubs),
+        ];
+        match Game::get_play(&mut cards)
+        {
+            Ok(Play::Bomb(cards)) => assert_eq!(cards.len(), 4),
+            other => panic!("expected Bomb, got {:?}", other.is_ok()),
+        }
+    }
+
+    #[test]
+    fn sequence()
+    {
+        let mut cards = vec![
+            spades(3), spades(4), spades(5), spades(6), spades(7),
+        ];
+        assert!(matches!(Game::get_play(&mut cards), Ok(Play::Sequence(_))));
+    }
+
+    #[test]
+    fn sequence_cannot_include_the_2()
+    {
+        // ranks 9..13 map to J,Q,K,A,2 - five consecutive ranks, but a run may not hold the 2 - reject
+        let mut cards = vec![
+            spades(9), spades(10), spades(11), spades(12), spades(13),
+        ];
+        assert!(Game::get_play(&mut cards).is_err());
+    }
+
+    #[test]
+    fn quad_two_single()
+    {
+        let mut cards = vec![
+            spades(9), card(9, Suit::Hearts), card(9, Suit::Diamonds), card(9, Suit::Clubs),
+            spades(4), spades(5),
+        ];
+        assert!(matches!(Game::get_play(&mut cards), Ok(Play::QuadTwoSingle { .. })));
+    }
+
+    #[test]
+    fn quad_two_pair()
+    {
+        let mut cards = vec![
+            spades(9), card(9, Suit::Hearts), card(9, Suit::Diamonds), card(9, Suit::Clubs),
+            spades(4), card(4, Suit::Hearts), spades(5), card(5, Suit::Hearts),
+        ];
+        assert!(matches!(Game::get_play(&mut cards), Ok(Play::QuadTwoPair { .. })));
+    }
+
+    #[test]
+    fn airplane_without_attachments()
+    {
+        let mut cards = vec![
+            spades(4), card(4, Suit::Hearts), card(4, Suit::Diamonds),
+            spades(5), card(5, Suit::Hearts), card(5, Suit::Diamonds),
+        ];
+        assert!(matches!(Game::get_play(&mut cards), Ok(Play::Airplane { .. })));
+    }
+
+    #[test]
+    fn airplane_with_attached_singles()
+    {
+        let mut cards = vec![
+            spades(4), card(4, Suit::Hearts), card(4, Suit::Diamonds),
+            spades(5), card(5, Suit::Hearts), card(5, Suit::Diamonds),
+            spades(10), spades(11),
+        ];
+        match Game::get_play(&mut cards)
+        {
+            Ok(Play::Airplane { triples, attachments }) =>
+            {
+                assert_eq!(triples.len(), 2);
+                assert_eq!(attachments.len(), 2);
+            },
+            other => panic!("expected Airplane, got {:?}", other.is_ok()),
+        }
+    }
+
+    #[test]
+    fn airplane_attachment_cannot_be_from_a_core_triple()
+    {
+        // four cards of rank 4 plus a triple of rank 5 must not be read as an airplane
+        // that "attaches" one of the rank-4 cards while the other three form a triple
+        let mut cards = vec![
+            spades(4), card(4, Suit::Hearts), card(4, Suit::Diamonds), card(4, Suit::Clubs),
+            spades(5), card(5, Suit::Hearts), card(5, Suit::Diamonds),
+        ];
+        assert!(Game::get_play(&mut cards).is_err());
+    }
+
+    #[test]
+    fn airplane_cannot_include_rank_2()
+    {
+        let mut cards = vec![
+            spades(12), card(12, Suit::Hearts), card(12, Suit::Diamonds),
+            spades(13), card(13, Suit::Hearts), card(13, Suit::Diamonds),
+        ];
+        assert!(Game::get_play(&mut cards).is_err());
+    }
+
+    fn play_of(cards: &mut [Card]) -> Play
+    {
+        Game::get_play(cards).unwrap()
+    }
+
+    #[test]
+    fn leader_may_play_anything()
+    {
+        let mut single = vec![spades(4)];
+        assert!(Game::is_valid_play(&Vec::new(), &play_of(&mut single), 0));
+    }
+
+    #[test]
+    fn fresh_trick_after_two_passes_may_play_anything()
+    {
+        let mut bomb = vec![spades(9), card(9, Suit::Hearts), card(9, Suit::Diamonds), card(9, Suit::Clubs)];
+        let sequence = vec![play_of(&mut bomb)];
+        let mut single = vec![spades(4)];
+        assert!(Game::is_valid_play(&sequence, &play_of(&mut single), 2));
+    }
+
+    #[test]
+    fn equal_rank_is_rejected()
+    {
+        let mut first = vec![spades(7), card(7, Suit::Hearts)];
+        let sequence = vec![play_of(&mut first)];
+        let mut second = vec![card(7, Suit::Diamonds), card(7, Suit::Clubs)];
+        assert!(!Game::is_valid_play(&sequence, &play_of(&mut second), 0));
+    }
+
+    #[test]
+    fn higher_pair_beats_lower_pair()
+    {
+        let mut first = vec![spades(7), card(7, Suit::Hearts)];
+        let sequence = vec![play_of(&mut first)];
+        let mut second = vec![card(8, Suit::Diamonds), card(8, Suit::Clubs)];
+        assert!(Game::is_valid_play(&sequence, &play_of(&mut second), 0));
+    }
+
+    #[test]
+    fn different_shape_is_rejected()
+    {
+        let mut first = vec![spades(7), card(7, Suit::Hearts)];
+        let sequence = vec![play_of(&mut first)];
+        let mut second = vec![card(8, Suit::Diamonds), card(8, Suit::Clubs), card(8, Suit::Spades)];
+        assert!(!Game::is_valid_play(&sequence, &play_of(&mut second), 0));
+    }
+
+    #[test]
+    fn bomb_beats_sequence()
+    {
+        let mut sequence_play = vec![spades(3), spades(4), spades(5), spades(6), spades(7)];
+        let history = vec![play_of(&mut sequence_play)];
+        let mut bomb = vec![spades(9), card(9, Suit::Hearts), card(9, Suit::Diamonds), card(9, Suit::Clubs)];
+        assert!(Game::is_valid_play(&history, &play_of(&mut bomb), 0));
+    }
+
+    #[test]
+    fn rocket_beats_bomb()
+    {
+        let mut bomb = vec![spades(9), card(9, Suit::Hearts), card(9, Suit::Diamonds), card(9, Suit::Clubs)];
+        let history = vec![play_of(&mut bomb)];
+        let mut rocket = vec![Card::BLACK_JOKER, Card::RED_JOKER];
+        assert!(Game::is_valid_play(&history, &play_of(&mut rocket), 0));
+    }
+
+    #[test]
+    fn nothing_beats_a_rocket()
+    {
+        let mut rocket = vec![Card::BLACK_JOKER, Card::RED_JOKER];
+        let history = vec![play_of(&mut rocket)];
+        let mut bomb = vec![spades(9), card(9, Suit::Hearts), card(9, Suit::Diamonds), card(9, Suit::Clubs)];
+        assert!(!Game::is_valid_play(&history, &play_of(&mut bomb), 0));
+    }
+
+    #[test]
+    fn bid_of_three_ends_the_auction_immediately()
+    {
+        let mut game = Game::new();
+        game.bid(0, 3).unwrap();
+        assert_eq!(game.get_landlord(), Some(0));
+        assert_eq!(game.get_multiplier(), 3);
+    }
+
+    #[test]
+    fn bid_must_exceed_the_current_highest_bid()
+    {
+        let mut game = Game::new();
+        game.bid(0, 2).unwrap();
+        assert!(game.bid(1, 2).is_err());
+    }
+
+    #[test]
+    fn auction_ends_once_the_other_two_players_pass()
+    {
+        let mut game = Game::new();
+        game.bid(0, 1).unwrap();
+        game.pass_bid(1).unwrap();
+        assert!(game.get_landlord().is_none());
+        game.pass_bid(2).unwrap();
+        assert_eq!(game.get_landlord(), Some(0));
+        assert_eq!(game.get_multiplier(), 1);
+    }
+
+    #[test]
+    fn all_players_passing_defaults_the_first_player_to_landlord()
+    {
+        let mut game = Game::new();
+        game.pass_bid(0).unwrap();
+        game.pass_bid(1).unwrap();
+        game.pass_bid(2).unwrap();
+        assert_eq!(game.get_landlord(), Some(0));
+    }
+
+    #[test]
+    fn out_of_turn_bid_is_rejected()
+    {
+        let mut game = Game::new();
+        assert!(game.bid(1, 1).is_err());
+    }
+
+    #[test]
+    fn out_of_turn_play_is_rejected()
+    {
+        let mut game = Game::new();
+        game.bid(0, 1).unwrap();
+        game.pass_bid(1).unwrap();
+        game.pass_bid(2).unwrap();
+        assert_eq!(game.get_landlord(), Some(0));
+
+        let mut single = vec![game.players[2].hand[0]];
+        assert!(game.play_cards(2, &mut single).is_err());
+    }
+
+    #[test]
+    fn out_of_turn_pass_is_rejected()
+    {
+        let mut game = Game::new();
+        game.bid(0, 1).unwrap();
+        game.pass_bid(1).unwrap();
+        game.pass_bid(2).unwrap();
+        assert_eq!(game.get_landlord(), Some(0));
+
+        assert!(game.pass(2).is_err());
+    }
+
+    #[test]
+    fn landlord_win_settles_score_by_stake()
+    {
+        let mut game = Game::new();
+        game.bid(0, 2).unwrap();
+        game.pass_bid(1).unwrap();
+        game.pass_bid(2).unwrap();
+        assert_eq!(game.get_multiplier(), 2);
+
+        // hand every card away except a single card for the landlord to win on
+        let winning_card = game.players[0].hand.pop().unwrap();
+        game.players[0].hand.clear();
+        game.players[0].hand.push(winning_card);
+
+        game.play_cards(0, &mut [winning_card]).unwrap();
+
+        // base bid 2, doubled to a multiplier of 4 by the spring (no peasant ever played)
+        assert_eq!(game.get_winner(), Some(0));
+        assert_eq!(game.get_scores()[0], 8);
+        assert_eq!(game.get_scores()[1], -8);
+        assert_eq!(game.get_scores()[2], -8);
+    }
+
+    #[test]
+    fn bomb_doubles_the_multiplier()
+    {
+        let mut game = Game::new();
+        game.bid(0, 1).unwrap();
+        game.pass_bid(1).unwrap();
+        game.pass_bid(2).unwrap();
+        assert_eq!(game.get_multiplier(), 1);
+
+        // give the landlord a guaranteed bomb, plus a spare card so the game isn't won
+        let bomb = vec![spades(9), card(9, Suit::Hearts), card(9, Suit::Diamonds), card(9, Suit::Clubs)];
+        game.players[0].hand = bomb.clone();
+        game.players[0].hand.push(spades(4));
+
+        game.play_cards(0, &mut bomb.clone()).unwrap();
+        assert_eq!(game.get_multiplier(), 2);
+    }
+}
@@ -0,0 +1,165 @@
+// Hosts a single `Game` over WebSockets so three remote clients can play a match together.
+//
+// Modeled on a tide + tide-websockets setup; pulling this module in (via the `server`
+// feature) additionally requires `serde`, `serde_json`, `tide`, `tide-websockets`, `uuid`,
+// `async-std`, and `futures-util` as dependencies.
+
+use crate::{Card, Game, Play};
+use async_std::sync::RwLock;
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tide_websockets::{Message, WebSocket, WebSocketConnection};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+pub enum ClientMessage
+{
+    Bid
+    {
+        amount: u8,
+    },
+    PassBid,
+    PlayCards
+    {
+        cards: Vec<Card>,
+    },
+    Pass,
+}
+
+#[derive(Serialize)]
+pub struct OpponentView
+{
+    pub player_idx: usize,
+    pub hand_count: usize,
+}
+
+#[derive(Serialize)]
+pub struct GameStateView
+{
+    pub hand: Vec<Card>,
+    pub opponents: Vec<OpponentView>,
+    pub play_sequence: Vec<Play>,
+    pub current_turn_idx: usize,
+    pub landlord: Option<usize>,
+    pub winner: Option<usize>,
+    pub scores: [i32; 3],
+    pub multiplier: u32,
+}
+
+impl GameStateView
+{
+    fn for_player(game: &Game, player_idx: usize) -> Self
+    {
+        let opponents = (0..3)
+            .filter(|idx| *idx != player_idx)
+            .map(|idx| OpponentView { player_idx: idx, hand_count: game.get_player(idx).hand.len() })
+            .collect();
+
+        GameStateView {
+            hand: game.get_player(player_idx).hand.clone(),
+            opponents,
+            play_sequence: game.get_play_sequence().clone(),
+            current_turn_idx: game.get_current_turn_idx(),
+            landlord: game.get_landlord(),
+            winner: game.get_winner(),
+            scores: game.get_scores(),
+            multiplier: game.get_multiplier(),
+        }
+    }
+}
+
+struct Session
+{
+    player_idx: usize,
+    connection: WebSocketConnection,
+}
+
+#[derive(Clone)]
+pub struct ServerState
+{
+    game: Arc<RwLock<Game>>,
+    sessions: Arc<RwLock<HashMap<Uuid, Session>>>,
+}
+
+pub fn app() -> tide::Server<ServerState>
+{
+    let state = ServerState {
+        game: Arc::new(RwLock::new(Game::new())),
+        sessions: Arc::new(RwLock::new(HashMap::new())),
+    };
+
+    let mut app = tide::with_state(state);
+    app.at("/ws/:player_idx").get(WebSocket::new(handle_connection));
+    app
+}
+
+async fn handle_connection(request: tide::Request<ServerState>, stream: WebSocketConnection) -> tide::Result<()>
+{
+    let player_idx: usize = request.param("player_idx")?
+        .parse()
+        .map_err(|_| tide::Error::from_str(400, "invalid player index"))?;
+    if player_idx >= 3
+    {
+        return Err(tide::Error::from_str(400, "invalid player index"));
+    }
+
+    let state = request.state().clone();
+    let session_id = Uuid::new_v4();
+    state.sessions.write().await.insert(session_id, Session { player_idx, connection: stream.clone() });
+
+    send_state(&state, player_idx).await?;
+
+    let mut stream = stream;
+    while let Some(Ok(Message::Text(text))) = stream.next().await
+    {
+        let Ok(message) = serde_json::from_str::<ClientMessage>(&text) else { continue };
+
+        // reject out-of-turn messages
+        if state.game.read().await.get_current_turn_idx() != player_idx
+        {
+            continue;
+        }
+
+        let result = {
+            let mut game = state.game.write().await;
+            match message
+            {
+                ClientMessage::Bid { amount } => game.bid(player_idx, amount),
+                ClientMessage::PassBid => game.pass_bid(player_idx),
+                ClientMessage::PlayCards { mut cards } => game.play_cards(player_idx, &mut cards),
+                ClientMessage::Pass => game.pass(player_idx),
+            }
+        };
+
+        if result.is_ok()
+        {
+            broadcast_state(&state).await?;
+        }
+    }
+
+    state.sessions.write().await.remove(&session_id);
+    Ok(())
+}
+
+async fn send_state(state: &ServerState, player_idx: usize) -> tide::Result<()>
+{
+    let view = GameStateView::for_player(&*state.game.read().await, player_idx);
+    if let Some(session) = state.sessions.read().await.values().find(|session| session.player_idx == player_idx)
+    {
+        session.connection.send_json(&view).await?;
+    }
+    Ok(())
+}
+
+async fn broadcast_state(state: &ServerState) -> tide::Result<()>
+{
+    let game = state.game.read().await;
+    for session in state.sessions.read().await.values()
+    {
+        session.connection.send_json(&GameStateView::for_player(&game, session.player_idx)).await?;
+    }
+    Ok(())
+}